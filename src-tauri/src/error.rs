@@ -0,0 +1,46 @@
+// Structured error type for the storage layer, replacing the old
+// `map_err(|e| e.to_string())` pattern so callers (and the frontend) can
+// distinguish not-found, IO, and serialization failures.
+
+use serde::Serialize;
+
+#[derive(Debug, thiserror::Error)]
+pub enum AppError {
+    #[error("note not found")]
+    NoteNotFound,
+    #[error("pdf not found")]
+    PdfNotFound,
+    #[error("failed to get app data directory")]
+    AppDataDirUnavailable,
+    #[error("no file was selected")]
+    PdfDialogCancelled,
+    #[error("file is not a valid PDF")]
+    InvalidPdf,
+    #[error("job serialization failed: {0}")]
+    Job(String),
+    #[error("markdown import failed: {0}")]
+    MarkdownImport(String),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Serialization(#[from] serde_json::Error),
+}
+
+// Tauri commands serialize their error variant to the frontend; we send the
+// human-readable message rather than leaking internal error shapes.
+impl Serialize for AppError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+// Lets `?` keep working in modules (markdown, search) that still bubble
+// storage errors up through a `Result<_, String>` command signature.
+impl From<AppError> for String {
+    fn from(err: AppError) -> Self {
+        err.to_string()
+    }
+}
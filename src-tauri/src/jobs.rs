@@ -0,0 +1,309 @@
+// Resumable background indexing jobs. Progress is persisted to `jobs/` as
+// MessagePack after every processed item, so a vault import or large PDF
+// import survives the app being killed mid-way and resumes where it left off.
+
+use crate::error::AppError;
+use crate::get_app_subdir;
+use crate::markdown;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub enum Job {
+    IndexVault {
+        dir: PathBuf,
+        processed: Vec<PathBuf>,
+    },
+    ImportPdf {
+        path: PathBuf,
+        page_cursor: i32,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct JobState {
+    pub id: String,
+    pub job: Job,
+}
+
+#[derive(Debug, Serialize, Clone)]
+struct JobProgress {
+    id: String,
+    percent: u32,
+}
+
+lazy_static! {
+    static ref CANCELLED: Mutex<HashSet<String>> = Mutex::new(HashSet::new());
+}
+
+fn now_id(prefix: &str) -> String {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_micros();
+    format!("{}_{}", prefix, now)
+}
+
+fn is_cancelled(id: &str) -> bool {
+    CANCELLED.lock().unwrap().contains(id)
+}
+
+fn clear_cancelled(id: &str) {
+    CANCELLED.lock().unwrap().remove(id);
+}
+
+fn job_file(jobs_dir: &Path, id: &str) -> PathBuf {
+    jobs_dir.join(format!("{}.job", id))
+}
+
+async fn persist(jobs_dir: &Path, state: &JobState) -> Result<(), AppError> {
+    let bytes = rmp_serde::to_vec(state).map_err(|e| AppError::Job(e.to_string()))?;
+    crate::write_atomic(&job_file(jobs_dir, &state.id), &bytes).await
+}
+
+async fn remove_job_file(jobs_dir: &Path, id: &str) {
+    let _ = tokio::fs::remove_file(job_file(jobs_dir, id)).await;
+}
+
+/// Lists every job with persisted (i.e. incomplete) state.
+#[tauri::command]
+pub async fn list_jobs() -> Result<Vec<JobState>, AppError> {
+    let jobs_dir = get_app_subdir("jobs").await?;
+    let mut jobs = Vec::new();
+
+    let mut entries = tokio::fs::read_dir(&jobs_dir).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("job") {
+            continue;
+        }
+        let bytes = tokio::fs::read(&path).await?;
+        match rmp_serde::from_slice::<JobState>(&bytes) {
+            Ok(state) => jobs.push(state),
+            Err(err) => tracing::warn!(path = %path.display(), error = %err, "skipping unreadable job"),
+        }
+    }
+
+    Ok(jobs)
+}
+
+/// Marks a job cancelled and drops its persisted state, so it won't resume.
+#[tauri::command]
+pub async fn cancel_job(id: String) -> Result<(), AppError> {
+    CANCELLED.lock().unwrap().insert(id.clone());
+    let jobs_dir = get_app_subdir("jobs").await?;
+    remove_job_file(&jobs_dir, &id).await;
+    Ok(())
+}
+
+/// Starts (in the background) a recursive import of every `.md` file under
+/// `dir`, returning the new job's id immediately.
+#[tauri::command]
+pub async fn start_vault_import(dir: String, app_handle: AppHandle) -> Result<String, AppError> {
+    let id = now_id("job");
+    let jobs_dir = get_app_subdir("jobs").await?;
+    let dir = PathBuf::from(dir);
+
+    let state = JobState {
+        id: id.clone(),
+        job: Job::IndexVault {
+            dir: dir.clone(),
+            processed: vec![],
+        },
+    };
+    persist(&jobs_dir, &state).await?;
+
+    tokio::spawn(run_index_vault_job(app_handle, jobs_dir, id.clone(), dir, vec![]));
+    Ok(id)
+}
+
+/// Starts (in the background) a page-by-page PDF import that survives the
+/// app quitting, unlike `import_pdf`'s inline extraction. Opens the same
+/// file picker as `import_pdf`, copies the file into content-addressed
+/// storage, then resumes one page at a time from `jobs/`.
+#[tauri::command]
+pub async fn start_pdf_import(app_handle: AppHandle) -> Result<String, AppError> {
+    let source_path = crate::pick_pdf_file().await.ok_or(AppError::PdfDialogCancelled)?;
+    let bytes = tokio::fs::read(&source_path).await?;
+    lopdf::Document::load_mem(&bytes).map_err(|_| AppError::InvalidPdf)?;
+
+    let pdfs_dir = get_app_subdir("pdfs").await?;
+    let dest_path = pdfs_dir.join(format!("{}.pdf", crate::content_hash(&bytes)));
+    tokio::fs::write(&dest_path, &bytes).await?;
+
+    let id = now_id("job");
+    let jobs_dir = get_app_subdir("jobs").await?;
+    let state = JobState {
+        id: id.clone(),
+        job: Job::ImportPdf {
+            path: dest_path.clone(),
+            page_cursor: 0,
+        },
+    };
+    persist(&jobs_dir, &state).await?;
+
+    tokio::spawn(run_import_pdf_job(app_handle, jobs_dir, id.clone(), dest_path, 0));
+    Ok(id)
+}
+
+async fn run_index_vault_job(
+    app_handle: AppHandle,
+    jobs_dir: PathBuf,
+    id: String,
+    dir: PathBuf,
+    mut processed: Vec<PathBuf>,
+) {
+    let files = crate::vault::collect_markdown_files(&dir);
+    let total = files.len().max(1);
+    let already: HashSet<PathBuf> = processed.iter().cloned().collect();
+
+    for file in files {
+        if is_cancelled(&id) {
+            break;
+        }
+        if already.contains(&file) {
+            continue;
+        }
+
+        if let Ok(text) = tokio::fs::read_to_string(&file).await {
+            let title = file
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or("Untitled")
+                .to_string();
+            let _ = markdown::import_markdown(text, title).await;
+        }
+
+        processed.push(file);
+
+        // Re-check cancellation before persisting: cancel_job already removed
+        // the job file, and persisting here would resurrect it for resume_all
+        // to pick back up on the next launch.
+        if is_cancelled(&id) {
+            break;
+        }
+
+        let state = JobState {
+            id: id.clone(),
+            job: Job::IndexVault {
+                dir: dir.clone(),
+                processed: processed.clone(),
+            },
+        };
+        let _ = persist(&jobs_dir, &state).await;
+
+        let percent = (processed.len() * 100 / total) as u32;
+        let _ = app_handle.emit_all("job-progress", JobProgress { id: id.clone(), percent });
+    }
+
+    if !is_cancelled(&id) {
+        remove_job_file(&jobs_dir, &id).await;
+    }
+    clear_cancelled(&id);
+}
+
+async fn run_import_pdf_job(
+    app_handle: AppHandle,
+    jobs_dir: PathBuf,
+    id: String,
+    path: PathBuf,
+    mut page_cursor: i32,
+) {
+    let Ok(bytes) = tokio::fs::read(&path).await else {
+        remove_job_file(&jobs_dir, &id).await;
+        return;
+    };
+    let Ok(document) = lopdf::Document::load_mem(&bytes) else {
+        remove_job_file(&jobs_dir, &id).await;
+        return;
+    };
+    let page_nums: Vec<u32> = document.get_pages().keys().copied().collect();
+    let total = page_nums.len().max(1);
+
+    let pdf_id = format!("pdf_{}", id);
+    let mut pdf = crate::load_pdf(pdf_id.clone()).await.unwrap_or_else(|_| {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        crate::PDFDocument {
+            id: pdf_id.clone(),
+            name: path
+                .file_name()
+                .and_then(|s| s.to_str())
+                .unwrap_or("document.pdf")
+                .to_string(),
+            path: path.to_string_lossy().to_string(),
+            pages: page_nums.len() as i32,
+            created_at: format!("{}", now),
+            updated_at: format!("{}", now),
+            annotations: Some(vec![]),
+            text_pages: Some(vec![]),
+        }
+    });
+    let mut text_pages = pdf.text_pages.clone().unwrap_or_default();
+    // A kill between save_pdf and persist leaves one page past the cursor.
+    text_pages.truncate(page_cursor as usize);
+
+    while (page_cursor as usize) < page_nums.len() {
+        if is_cancelled(&id) {
+            break;
+        }
+
+        let page_num = page_nums[page_cursor as usize];
+        text_pages.push(document.extract_text(&[page_num]).unwrap_or_default());
+        page_cursor += 1;
+
+        pdf.text_pages = Some(text_pages.clone());
+        let _ = crate::save_pdf(pdf.clone()).await;
+
+        // Re-check cancellation before persisting: cancel_job already removed
+        // the job file, and persisting here would resurrect it for resume_all
+        // to pick back up on the next launch.
+        if is_cancelled(&id) {
+            break;
+        }
+
+        let state = JobState {
+            id: id.clone(),
+            job: Job::ImportPdf {
+                path: path.clone(),
+                page_cursor,
+            },
+        };
+        let _ = persist(&jobs_dir, &state).await;
+
+        let percent = (page_cursor as usize * 100 / total) as u32;
+        let _ = app_handle.emit_all("job-progress", JobProgress { id: id.clone(), percent });
+    }
+
+    if (page_cursor as usize) >= page_nums.len() {
+        remove_job_file(&jobs_dir, &id).await;
+    }
+    clear_cancelled(&id);
+}
+
+/// Scans `jobs/` for incomplete work left behind by a previous run and
+/// resumes each one from its saved cursor.
+pub async fn resume_all(app_handle: AppHandle) -> Result<(), AppError> {
+    let jobs_dir = get_app_subdir("jobs").await?;
+
+    for state in list_jobs().await? {
+        let app_handle = app_handle.clone();
+        let jobs_dir = jobs_dir.clone();
+        match state.job {
+            Job::IndexVault { dir, processed } => {
+                tokio::spawn(run_index_vault_job(app_handle, jobs_dir, state.id, dir, processed));
+            }
+            Job::ImportPdf { path, page_cursor } => {
+                tokio::spawn(run_import_pdf_job(app_handle, jobs_dir, state.id, path, page_cursor));
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -2,9 +2,23 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 use serde::{Deserialize, Serialize};
-use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 use tauri::Manager;
+use tokio::io::AsyncWriteExt;
+
+mod error;
+mod jobs;
+mod markdown;
+mod search;
+mod vault;
+mod watcher;
+
+use error::AppError;
+use jobs::{cancel_job, list_jobs, start_pdf_import, start_vault_import};
+use markdown::{export_note_markdown, import_markdown};
+use search::search_notes;
+use vault::import_directory;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Block {
@@ -45,6 +59,8 @@ pub struct PDFDocument {
     pub created_at: String,
     pub updated_at: String,
     pub annotations: Option<Vec<PDFAnnotation>>,
+    /// Per-page extracted text, used to make imported PDFs full-text searchable.
+    pub text_pages: Option<Vec<String>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -57,116 +73,158 @@ pub struct PDFAnnotation {
     pub color: Option<String>,
 }
 
-fn get_notes_dir() -> Result<PathBuf, String> {
+/// Resolves (creating if needed) a named subdirectory of the app's data dir,
+/// e.g. `notes`, `pdfs`, or `jobs`.
+pub(crate) async fn get_app_subdir(name: &str) -> Result<PathBuf, AppError> {
     let app_dir = dirs::data_dir()
-        .ok_or("Failed to get app data directory")?
+        .ok_or(AppError::AppDataDirUnavailable)?
         .join("flow-notes");
-    let notes_dir = app_dir.join("notes");
-    
-    if !notes_dir.exists() {
-        fs::create_dir_all(&notes_dir).map_err(|e| e.to_string())?;
+    let subdir = app_dir.join(name);
+
+    if !subdir.exists() {
+        tokio::fs::create_dir_all(&subdir).await?;
     }
-    
-    Ok(notes_dir)
+
+    Ok(subdir)
 }
 
-fn get_pdfs_dir() -> Result<PathBuf, String> {
-    let app_dir = dirs::data_dir()
-        .ok_or("Failed to get app data directory")?
-        .join("flow-notes");
-    let pdfs_dir = app_dir.join("pdfs");
-    
-    if !pdfs_dir.exists() {
-        fs::create_dir_all(&pdfs_dir).map_err(|e| e.to_string())?;
-    }
-    
-    Ok(pdfs_dir)
+async fn get_notes_dir() -> Result<PathBuf, AppError> {
+    get_app_subdir("notes").await
+}
+
+async fn get_pdfs_dir() -> Result<PathBuf, AppError> {
+    get_app_subdir("pdfs").await
+}
+
+static ID_SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+/// Generates a collision-resistant id: a millisecond timestamp combined with
+/// a process-wide monotonic counter, so importing many items within the same
+/// millisecond (e.g. a batch vault import) never produces two equal ids.
+pub(crate) fn generate_id(prefix: &str) -> String {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis();
+    let seq = ID_SEQUENCE.fetch_add(1, Ordering::Relaxed);
+    format!("{}_{}_{}", prefix, now, seq)
+}
+
+static TMP_SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+/// Writes `contents` to `path` crash-safely: serialize to a sibling
+/// `.tmp` file, fsync it, then rename over the destination. Readers never
+/// observe a partially written file, even if the process is killed mid-write.
+/// The tmp file name is unique per call, so two concurrent writers to the
+/// same destination never race on the same in-flight tmp file.
+pub(crate) async fn write_atomic(path: &Path, contents: &[u8]) -> Result<(), AppError> {
+    let unique = format!(
+        "{}.{}.tmp",
+        std::process::id(),
+        TMP_SEQUENCE.fetch_add(1, Ordering::Relaxed)
+    );
+    let tmp_path = path.with_extension(unique);
+
+    let mut file = tokio::fs::File::create(&tmp_path).await?;
+    file.write_all(contents).await?;
+    file.sync_all().await?;
+    drop(file);
+
+    // Mark before the rename lands so the watcher thread, which may observe
+    // the event before this function returns, knows the write was ours.
+    watcher::mark_written(path.to_path_buf());
+    tokio::fs::rename(&tmp_path, path).await?;
+    Ok(())
 }
 
 #[tauri::command]
-fn save_note(note: Note) -> Result<(), String> {
-    let notes_dir = get_notes_dir()?;
+async fn save_note(note: Note) -> Result<(), AppError> {
+    let notes_dir = get_notes_dir().await?;
     let note_file = notes_dir.join(format!("{}.json", note.id));
-    
-    let json = serde_json::to_string_pretty(&note)
-        .map_err(|e| e.to_string())?;
-    
-    fs::write(note_file, json).map_err(|e| e.to_string())?;
+
+    let json = serde_json::to_vec_pretty(&note)?;
+    write_atomic(&note_file, &json).await?;
+
+    search::index_note(&note);
     Ok(())
 }
 
 #[tauri::command]
-fn load_note(note_id: String) -> Result<Note, String> {
-    let notes_dir = get_notes_dir()?;
+async fn load_note(note_id: String) -> Result<Note, AppError> {
+    let notes_dir = get_notes_dir().await?;
     let note_file = notes_dir.join(format!("{}.json", note_id));
-    
+
     if !note_file.exists() {
-        return Err("Note not found".to_string());
+        return Err(AppError::NoteNotFound);
     }
-    
-    let content = fs::read_to_string(note_file).map_err(|e| e.to_string())?;
-    let note: Note = serde_json::from_str(&content).map_err(|e| e.to_string())?;
+
+    let content = tokio::fs::read_to_string(note_file).await?;
+    let note: Note = serde_json::from_str(&content)?;
     Ok(note)
 }
 
 #[tauri::command]
-fn list_notes() -> Result<Vec<NoteMetadata>, String> {
-    let notes_dir = get_notes_dir()?;
+async fn list_notes() -> Result<Vec<NoteMetadata>, AppError> {
+    let notes_dir = get_notes_dir().await?;
     let mut notes = Vec::new();
-    
-    for entry in fs::read_dir(notes_dir).map_err(|e| e.to_string())? {
-        let entry = entry.map_err(|e| e.to_string())?;
+
+    let mut entries = tokio::fs::read_dir(notes_dir).await?;
+    while let Some(entry) = entries.next_entry().await? {
         let path = entry.path();
-        
+
         if path.extension().and_then(|s| s.to_str()) == Some("json") {
-            let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
-            if let Ok(note) = serde_json::from_str::<Note>(&content) {
-                let metadata = NoteMetadata {
+            let content = tokio::fs::read_to_string(&path).await?;
+            match serde_json::from_str::<Note>(&content) {
+                Ok(note) => notes.push(NoteMetadata {
                     id: note.id,
                     title: note.title,
                     created_at: note.created_at,
                     updated_at: note.updated_at,
                     tags: note.tags,
-                };
-                notes.push(metadata);
+                }),
+                Err(err) => {
+                    tracing::warn!(path = %path.display(), error = %err, "skipping unreadable note")
+                }
             }
         }
     }
-    
+
     // Sort by updated_at descending
     notes.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
     Ok(notes)
 }
 
 #[tauri::command]
-fn delete_note(note_id: String) -> Result<(), String> {
-    let notes_dir = get_notes_dir()?;
+async fn delete_note(note_id: String) -> Result<(), AppError> {
+    let notes_dir = get_notes_dir().await?;
     let note_file = notes_dir.join(format!("{}.json", note_id));
-    
+
     if note_file.exists() {
-        fs::remove_file(note_file).map_err(|e| e.to_string())?;
+        watcher::mark_written(note_file.clone());
+        tokio::fs::remove_file(note_file).await?;
     }
-    
+    search::remove_note(&note_id);
+
     Ok(())
 }
 
 #[tauri::command]
-fn create_note(title: String) -> Result<Note, String> {
+async fn create_note(title: String) -> Result<Note, AppError> {
     let now = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .unwrap()
         .as_secs();
-    
+
     // Check for duplicate titles and add a number if needed
-    let existing_notes = list_notes()?;
+    let existing_notes = list_notes().await?;
     let mut final_title = title.clone();
     let mut counter = 1;
-    
+
     while existing_notes.iter().any(|note| note.title == final_title) {
         final_title = format!("{} {}", title, counter);
         counter += 1;
     }
-    
+
     let note = Note {
         id: format!("note_{}", now),
         title: final_title,
@@ -185,101 +243,151 @@ fn create_note(title: String) -> Result<Note, String> {
         updated_at: format!("{}", now),
         tags: None,
     };
-    
-    save_note(note.clone())?;
+
+    save_note(note.clone()).await?;
     Ok(note)
 }
 
+/// Opens a native file picker restricted to `.pdf` files, resolving to
+/// `None` if the user cancels.
+async fn pick_pdf_file() -> Option<PathBuf> {
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    tauri::api::dialog::FileDialogBuilder::new()
+        .add_filter("PDF", &["pdf"])
+        .pick_file(move |path| {
+            let _ = tx.send(path);
+        });
+    rx.await.ok().flatten()
+}
+
+fn content_hash(bytes: &[u8]) -> String {
+    use sha2::{Digest, Sha256};
+    Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Imports a PDF and extracts all of its text inline before returning.
+/// Fine for small files, but blocks the command thread for the whole
+/// extraction — for large PDFs, use `start_pdf_import` instead, which does
+/// the same work page-by-page as a resumable background job.
 #[tauri::command]
-fn import_pdf() -> Result<PDFDocument, String> {
+async fn import_pdf() -> Result<PDFDocument, AppError> {
+    let source_path = pick_pdf_file().await.ok_or(AppError::PdfDialogCancelled)?;
+    let bytes = tokio::fs::read(&source_path).await?;
+
+    let document = lopdf::Document::load_mem(&bytes).map_err(|_| AppError::InvalidPdf)?;
+    let pages = document.get_pages();
+    let text_pages: Vec<String> = pages
+        .keys()
+        .map(|&page_num| document.extract_text(&[page_num]).unwrap_or_default())
+        .collect();
+
+    let pdfs_dir = get_pdfs_dir().await?;
+    let dest_path = pdfs_dir.join(format!("{}.pdf", content_hash(&bytes)));
+    tokio::fs::write(&dest_path, &bytes).await?;
+
     let now = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .unwrap()
         .as_secs();
-    
-    // This would normally open a file dialog, but for now we'll create a placeholder
+    let name = source_path
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap_or("document.pdf")
+        .to_string();
+
     let pdf = PDFDocument {
         id: format!("pdf_{}", now),
-        name: "Sample PDF".to_string(),
-        path: "/path/to/sample.pdf".to_string(),
-        pages: 1,
+        name,
+        path: dest_path.to_string_lossy().to_string(),
+        pages: pages.len() as i32,
         created_at: format!("{}", now),
         updated_at: format!("{}", now),
         annotations: Some(vec![]),
+        text_pages: Some(text_pages),
     };
-    
-    save_pdf(pdf.clone())?;
+
+    save_pdf(pdf.clone()).await?;
     Ok(pdf)
 }
 
 #[tauri::command]
-fn save_pdf(pdf: PDFDocument) -> Result<(), String> {
-    let pdfs_dir = get_pdfs_dir()?;
+async fn save_pdf(pdf: PDFDocument) -> Result<(), AppError> {
+    let pdfs_dir = get_pdfs_dir().await?;
     let pdf_file = pdfs_dir.join(format!("{}.json", pdf.id));
-    
-    let json = serde_json::to_string_pretty(&pdf)
-        .map_err(|e| e.to_string())?;
-    
-    fs::write(pdf_file, json).map_err(|e| e.to_string())?;
+
+    let json = serde_json::to_vec_pretty(&pdf)?;
+    write_atomic(&pdf_file, &json).await?;
+
+    if let Some(text_pages) = &pdf.text_pages {
+        search::index_pdf_text(&pdf.id, text_pages);
+    }
     Ok(())
 }
 
 #[tauri::command]
-fn load_pdf(pdf_id: String) -> Result<PDFDocument, String> {
-    let pdfs_dir = get_pdfs_dir()?;
+async fn load_pdf(pdf_id: String) -> Result<PDFDocument, AppError> {
+    let pdfs_dir = get_pdfs_dir().await?;
     let pdf_file = pdfs_dir.join(format!("{}.json", pdf_id));
-    
+
     if !pdf_file.exists() {
-        return Err("PDF not found".to_string());
+        return Err(AppError::PdfNotFound);
     }
-    
-    let content = fs::read_to_string(pdf_file).map_err(|e| e.to_string())?;
-    let pdf: PDFDocument = serde_json::from_str(&content).map_err(|e| e.to_string())?;
+
+    let content = tokio::fs::read_to_string(pdf_file).await?;
+    let pdf: PDFDocument = serde_json::from_str(&content)?;
     Ok(pdf)
 }
 
 #[tauri::command]
-fn list_pdfs() -> Result<Vec<PDFDocument>, String> {
-    let pdfs_dir = get_pdfs_dir()?;
+async fn list_pdfs() -> Result<Vec<PDFDocument>, AppError> {
+    let pdfs_dir = get_pdfs_dir().await?;
     let mut pdfs = Vec::new();
-    
-    for entry in fs::read_dir(pdfs_dir).map_err(|e| e.to_string())? {
-        let entry = entry.map_err(|e| e.to_string())?;
+
+    let mut entries = tokio::fs::read_dir(pdfs_dir).await?;
+    while let Some(entry) = entries.next_entry().await? {
         let path = entry.path();
-        
+
         if path.extension().and_then(|s| s.to_str()) == Some("json") {
-            let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
-            if let Ok(pdf) = serde_json::from_str::<PDFDocument>(&content) {
-                pdfs.push(pdf);
+            let content = tokio::fs::read_to_string(&path).await?;
+            match serde_json::from_str::<PDFDocument>(&content) {
+                Ok(pdf) => pdfs.push(pdf),
+                Err(err) => {
+                    tracing::warn!(path = %path.display(), error = %err, "skipping unreadable pdf")
+                }
             }
         }
     }
-    
+
     // Sort by updated_at descending
     pdfs.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
     Ok(pdfs)
 }
 
 #[tauri::command]
-fn delete_pdf(pdf_id: String) -> Result<(), String> {
-    let pdfs_dir = get_pdfs_dir()?;
+async fn delete_pdf(pdf_id: String) -> Result<(), AppError> {
+    let pdfs_dir = get_pdfs_dir().await?;
     let pdf_file = pdfs_dir.join(format!("{}.json", pdf_id));
-    
+
     if pdf_file.exists() {
-        fs::remove_file(pdf_file).map_err(|e| e.to_string())?;
+        watcher::mark_written(pdf_file.clone());
+        tokio::fs::remove_file(pdf_file).await?;
     }
-    
+    search::remove_note(&pdf_id);
+
     Ok(())
 }
 
 #[tauri::command]
-fn save_pdf_annotation(pdf_id: String, annotation: PDFAnnotation) -> Result<(), String> {
-    let mut pdf = load_pdf(pdf_id)?;
-    
+async fn save_pdf_annotation(pdf_id: String, annotation: PDFAnnotation) -> Result<(), AppError> {
+    let mut pdf = load_pdf(pdf_id).await?;
+
     if pdf.annotations.is_none() {
         pdf.annotations = Some(vec![]);
     }
-    
+
     if let Some(ref mut annotations) = pdf.annotations {
         // Update existing annotation or add new one
         if let Some(existing_index) = annotations.iter().position(|a| a.id == annotation.id) {
@@ -288,18 +396,28 @@ fn save_pdf_annotation(pdf_id: String, annotation: PDFAnnotation) -> Result<(),
             annotations.push(annotation);
         }
     }
-    
+
     let now = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .unwrap()
         .as_secs();
     pdf.updated_at = format!("{}", now);
-    save_pdf(pdf)?;
+    if let Some(annotations) = &pdf.annotations {
+        search::index_pdf_annotations(&pdf.id, annotations);
+    }
+    save_pdf(pdf).await?;
     Ok(())
 }
 
 fn main() {
+    tracing_subscriber::fmt::init();
+
     tauri::Builder::default()
+        .setup(|app| {
+            watcher::start(app.handle());
+            tauri::async_runtime::spawn(jobs::resume_all(app.handle()));
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             save_note,
             load_note,
@@ -311,9 +429,16 @@ fn main() {
             load_pdf,
             list_pdfs,
             delete_pdf,
-            save_pdf_annotation
+            save_pdf_annotation,
+            export_note_markdown,
+            import_markdown,
+            search_notes,
+            start_vault_import,
+            start_pdf_import,
+            list_jobs,
+            cancel_job,
+            import_directory
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
-
@@ -0,0 +1,269 @@
+// Markdown import/export for notes, including Obsidian-style wikilink resolution.
+
+use crate::{list_notes, load_note, save_note, Block, Note};
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
+use regex::Regex;
+
+/// The maximum depth an `![[...]]` embed may recurse before we bail out,
+/// so that two notes embedding each other can't blow the stack.
+const MAX_EMBED_DEPTH: u32 = 10;
+
+const PATH_ENCODE_SET: &AsciiSet = &CONTROLS.add(b' ').add(b'(').add(b')').add(b'%');
+
+/// Matches both `[[file#block|label]]` links and `![[file#block|label]]` embeds.
+fn wikilink_regex() -> Regex {
+    Regex::new(r"(?P<embed>!)?\[\[(?P<inner>[^\]]+)\]\]").unwrap()
+}
+
+/// Splits the text inside `[[ ... ]]` into its `file`, `block`, and `label` parts.
+fn inner_link_regex() -> Regex {
+    Regex::new(r"^(?P<file>[^#|]+)(#(?P<block>.+?))??(\|(?P<label>.+?))??$").unwrap()
+}
+
+#[tauri::command]
+pub async fn export_note_markdown(note_id: String) -> Result<String, String> {
+    let note = load_note(note_id).await?;
+    let mut out = String::new();
+    blocks_to_markdown(&note.blocks, 0, &mut out);
+    Ok(out)
+}
+
+fn blocks_to_markdown(blocks: &[Block], depth: usize, out: &mut String) {
+    let mut sorted: Vec<&Block> = blocks.iter().collect();
+    sorted.sort_by_key(|b| b.order);
+
+    for block in sorted {
+        let indent = "  ".repeat(depth);
+        match block.r#type.as_str() {
+            "heading" => out.push_str(&format!("{}# {}\n", indent, block.content)),
+            "todo" | "checkbox" => {
+                let mark = if block.checked.unwrap_or(false) { "x" } else { " " };
+                out.push_str(&format!("{}- [{}] {}\n", indent, mark, block.content));
+            }
+            _ => out.push_str(&format!("{}- {}\n", indent, block.content)),
+        }
+
+        if let Some(children) = &block.children {
+            blocks_to_markdown(children, depth + 1, out);
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn import_markdown(text: String, title: String) -> Result<Note, String> {
+    let resolved = resolve_links(&text, 0).await?;
+
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+
+    let blocks = parse_markdown_blocks(&resolved, now);
+
+    let note = Note {
+        id: crate::generate_id("note"),
+        title,
+        blocks,
+        created_at: format!("{}", now),
+        updated_at: format!("{}", now),
+        tags: None,
+    };
+
+    save_note(note.clone()).await?;
+    Ok(note)
+}
+
+/// Walks a Markdown document and turns each line into a `Block`, nesting by indentation.
+fn parse_markdown_blocks(text: &str, seed: u64) -> Vec<Block> {
+    let mut root: Vec<Block> = Vec::new();
+    // Stack of (indent level, path of indices into `root`/children) for the
+    // most recently seen block at each depth.
+    let mut stack: Vec<(usize, Vec<usize>)> = Vec::new();
+    let mut order = 0i32;
+    let mut next_id = seed + 1;
+
+    for raw_line in text.lines() {
+        if raw_line.trim().is_empty() {
+            continue;
+        }
+
+        let indent = raw_line.chars().take_while(|c| *c == ' ').count() / 2;
+        let line = raw_line.trim_start();
+
+        let block = if let Some(rest) = line.strip_prefix("# ") {
+            new_block(next_id, "heading", rest, None, order)
+        } else if let Some(rest) = line.strip_prefix("- [x] ") {
+            new_block(next_id, "todo", rest, Some(true), order)
+        } else if let Some(rest) = line.strip_prefix("- [ ] ") {
+            new_block(next_id, "todo", rest, Some(false), order)
+        } else if let Some(rest) = line.strip_prefix("- ") {
+            new_block(next_id, "paragraph", rest, None, order)
+        } else {
+            new_block(next_id, "paragraph", line, None, order)
+        };
+        next_id += 1;
+        order += 1;
+
+        stack.retain(|(level, _)| *level < indent);
+
+        if indent == 0 || stack.is_empty() {
+            root.push(block);
+            stack.push((0, vec![root.len() - 1]));
+        } else {
+            let parent_path = stack.last().unwrap().1.clone();
+            let parent = get_block_mut(&mut root, &parent_path);
+            let children = parent.children.get_or_insert_with(Vec::new);
+            children.push(block);
+            let mut path = parent_path;
+            path.push(children.len() - 1);
+            stack.push((indent, path));
+        }
+    }
+
+    root
+}
+
+fn get_block_mut<'a>(root: &'a mut [Block], path: &[usize]) -> &'a mut Block {
+    let mut blocks = root;
+    let mut idx = path[0];
+    for &next in &path[1..] {
+        blocks = blocks[idx].children.as_mut().unwrap();
+        idx = next;
+    }
+    &mut blocks[idx]
+}
+
+fn new_block(id: u64, r#type: &str, content: &str, checked: Option<bool>, order: i32) -> Block {
+    Block {
+        id: format!("block_{}", id),
+        r#type: r#type.to_string(),
+        content: content.to_string(),
+        checked,
+        file_path: None,
+        children: None,
+        order,
+    }
+}
+
+/// Replaces `[[...]]` links with `note://` URIs and inlines `![[...]]` embeds,
+/// recursing up to `MAX_EMBED_DEPTH` to guard against embed cycles.
+///
+/// Boxed because async fns can't recurse directly without indirection.
+fn resolve_links(text: &str, depth: u32) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<String, String>> + '_>> {
+    Box::pin(async move {
+        if depth >= MAX_EMBED_DEPTH {
+            return Ok(text.to_string());
+        }
+
+        let link_re = wikilink_regex();
+        let inner_re = inner_link_regex();
+        let notes = list_notes().await?;
+
+        let mut out = String::with_capacity(text.len());
+        let mut last_end = 0;
+
+        for caps in link_re.captures_iter(text) {
+            let whole = caps.get(0).unwrap();
+            out.push_str(&text[last_end..whole.start()]);
+            last_end = whole.end();
+
+            let inner = &caps["inner"];
+            let is_embed = caps.name("embed").is_some();
+
+            let Some(inner_caps) = inner_re.captures(inner) else {
+                out.push_str(whole.as_str());
+                continue;
+            };
+
+            let file = inner_caps["file"].trim();
+            let block_id = inner_caps.name("block").map(|m| m.as_str().trim());
+            let label = inner_caps.name("label").map(|m| m.as_str().trim());
+
+            let Some(target) = notes.iter().find(|n| n.title == file) else {
+                // Unresolvable link: leave the original text untouched.
+                out.push_str(whole.as_str());
+                continue;
+            };
+
+            if is_embed {
+                let embedded = load_note(target.id.clone()).await?;
+                let mut rendered = String::new();
+                blocks_to_markdown(&embedded.blocks, 0, &mut rendered);
+                let rendered = resolve_links(&rendered, depth + 1).await?;
+                out.push_str(&rendered);
+            } else {
+                let mut path = target.id.clone();
+                if let Some(block_id) = block_id {
+                    path.push('/');
+                    path.push_str(block_id);
+                }
+                let encoded_path = utf8_percent_encode(&path, PATH_ENCODE_SET).to_string();
+                let display = label.unwrap_or(file);
+                out.push_str(&format!("[{}](note://{})", display, encoded_path));
+            }
+        }
+
+        out.push_str(&text[last_end..]);
+        Ok(out)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_markdown_blocks_nests_by_indentation() {
+        let text = "# Heading\n- [ ] todo\n  - child\n- [x] done\n";
+        let blocks = parse_markdown_blocks(text, 0);
+
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(blocks[0].r#type, "heading");
+        assert_eq!(blocks[0].content, "Heading");
+
+        assert_eq!(blocks[1].r#type, "todo");
+        assert_eq!(blocks[1].checked, Some(false));
+        let children = blocks[1].children.as_ref().expect("nested child block");
+        assert_eq!(children.len(), 1);
+        assert_eq!(children[0].content, "child");
+
+        assert_eq!(blocks[2].checked, Some(true));
+    }
+
+    #[test]
+    fn blocks_to_markdown_round_trips_todos_and_headings() {
+        let text = "# Heading\n- [ ] todo\n- [x] done\n";
+        let blocks = parse_markdown_blocks(text, 0);
+
+        let mut out = String::new();
+        blocks_to_markdown(&blocks, 0, &mut out);
+
+        assert_eq!(out, text);
+    }
+
+    #[test]
+    fn wikilink_regex_distinguishes_links_from_embeds() {
+        let re = wikilink_regex();
+        let caps = re.captures("see [[Other Note#block|Label]]").unwrap();
+        assert!(caps.name("embed").is_none());
+        assert_eq!(&caps["inner"], "Other Note#block|Label");
+
+        let caps = re.captures("![[Other Note]]").unwrap();
+        assert!(caps.name("embed").is_some());
+        assert_eq!(&caps["inner"], "Other Note");
+    }
+
+    #[test]
+    fn inner_link_regex_splits_file_block_and_label() {
+        let re = inner_link_regex();
+        let caps = re.captures("Other Note#block|Label").unwrap();
+        assert_eq!(&caps["file"], "Other Note");
+        assert_eq!(caps.name("block").unwrap().as_str(), "block");
+        assert_eq!(caps.name("label").unwrap().as_str(), "Label");
+
+        let caps = re.captures("Other Note").unwrap();
+        assert_eq!(&caps["file"], "Other Note");
+        assert!(caps.name("block").is_none());
+        assert!(caps.name("label").is_none());
+    }
+}
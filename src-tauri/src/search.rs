@@ -0,0 +1,370 @@
+// In-process full-text search over note blocks and PDF annotations.
+
+use crate::{list_notes, list_pdfs, load_note, Block, Note, PDFDocument};
+use lazy_static::lazy_static;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use unicode_segmentation::UnicodeSegmentation;
+
+#[derive(Debug, Clone)]
+struct Posting {
+    note_id: String,
+    block_id: String,
+    term_frequency: u32,
+}
+
+#[derive(Default)]
+struct SearchIndex {
+    /// term -> postings list
+    postings: HashMap<String, Vec<Posting>>,
+    /// (note_id, block_id) -> the block's raw text, used for snippets
+    block_text: HashMap<(String, String), String>,
+    /// note_id -> tags, used by the tag filter
+    note_tags: HashMap<String, Vec<String>>,
+    /// total indexed documents (notes), used as N in the TF-IDF formula
+    total_docs: usize,
+    built: bool,
+}
+
+lazy_static! {
+    static ref INDEX: Mutex<SearchIndex> = Mutex::new(SearchIndex::default());
+}
+
+fn tokenize(text: &str) -> Vec<String> {
+    text.unicode_words().map(|w| w.to_lowercase()).collect()
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct SearchResult {
+    pub note_id: String,
+    pub block_id: String,
+    pub score: f64,
+    pub snippet: String,
+}
+
+impl SearchIndex {
+    fn remove_note(&mut self, note_id: &str) {
+        for postings in self.postings.values_mut() {
+            postings.retain(|p| p.note_id != note_id);
+        }
+        self.block_text.retain(|(n, _), _| n != note_id);
+        if self.note_tags.remove(note_id).is_some() {
+            self.total_docs = self.total_docs.saturating_sub(1);
+        }
+    }
+
+    fn index_blocks(&mut self, note_id: &str, blocks: &[Block]) {
+        for block in blocks {
+            self.index_text(note_id, &block.id, &block.content);
+            if let Some(children) = &block.children {
+                self.index_blocks(note_id, children);
+            }
+        }
+    }
+
+    /// Drops a single block's postings without touching the rest of its
+    /// document, so re-indexing the same block is idempotent instead of
+    /// piling up duplicate postings.
+    fn remove_block(&mut self, note_id: &str, block_id: &str) {
+        for postings in self.postings.values_mut() {
+            postings.retain(|p| !(p.note_id == note_id && p.block_id == block_id));
+        }
+        self.block_text
+            .remove(&(note_id.to_string(), block_id.to_string()));
+    }
+
+    fn index_text(&mut self, note_id: &str, block_id: &str, text: &str) {
+        self.remove_block(note_id, block_id);
+        if text.trim().is_empty() {
+            return;
+        }
+        self.block_text
+            .insert((note_id.to_string(), block_id.to_string()), text.to_string());
+
+        let mut counts: HashMap<String, u32> = HashMap::new();
+        for term in tokenize(text) {
+            *counts.entry(term).or_insert(0) += 1;
+        }
+        for (term, term_frequency) in counts {
+            self.postings.entry(term).or_default().push(Posting {
+                note_id: note_id.to_string(),
+                block_id: block_id.to_string(),
+                term_frequency,
+            });
+        }
+    }
+
+    fn index_note(&mut self, note: &Note) {
+        self.remove_note(&note.id);
+        self.note_tags
+            .insert(note.id.clone(), note.tags.clone().unwrap_or_default());
+        self.total_docs += 1;
+        self.index_blocks(&note.id, &note.blocks);
+    }
+
+    fn index_pdf(&mut self, pdf: &PDFDocument) {
+        self.remove_note(&pdf.id);
+        self.note_tags.insert(pdf.id.clone(), vec![]);
+        self.total_docs += 1;
+        if let Some(text_pages) = &pdf.text_pages {
+            for (page_num, text) in text_pages.iter().enumerate() {
+                self.index_text(&pdf.id, &format!("page_{}", page_num), text);
+            }
+        }
+        if let Some(annotations) = &pdf.annotations {
+            for annotation in annotations {
+                if let Some(content) = &annotation.content {
+                    self.index_text(&pdf.id, &annotation.id, content);
+                }
+            }
+        }
+    }
+
+    fn document_frequency(&self, term: &str) -> usize {
+        match self.postings.get(term) {
+            Some(postings) => postings
+                .iter()
+                .map(|p| p.note_id.as_str())
+                .collect::<HashSet<_>>()
+                .len(),
+            None => 0,
+        }
+    }
+
+    fn rebuild_from(&mut self, notes: Vec<Note>, pdfs: Vec<PDFDocument>) {
+        self.postings.clear();
+        self.block_text.clear();
+        self.note_tags.clear();
+        self.total_docs = 0;
+
+        for note in &notes {
+            self.index_note(note);
+        }
+        for pdf in &pdfs {
+            self.index_pdf(pdf);
+        }
+
+        self.built = true;
+    }
+}
+
+/// Walks every note and PDF once to populate the index, if it hasn't been built yet.
+async fn ensure_built() -> Result<(), String> {
+    if INDEX.lock().unwrap().built {
+        return Ok(());
+    }
+
+    let mut notes = Vec::new();
+    for metadata in list_notes().await? {
+        notes.push(load_note(metadata.id).await?);
+    }
+    let pdfs = list_pdfs().await?;
+
+    let mut index = INDEX.lock().unwrap();
+    if !index.built {
+        index.rebuild_from(notes, pdfs);
+    }
+    Ok(())
+}
+
+/// Indexes (or re-indexes) a single note's blocks. Called from `save_note`
+/// so edits stay searchable without a full rebuild.
+pub fn index_note(note: &Note) {
+    let mut index = INDEX.lock().unwrap();
+    if !index.built {
+        return; // a full rebuild will pick this note up the first time search runs
+    }
+    index.index_note(note);
+}
+
+/// Drops a note's entries from the index. Called from `delete_note`.
+pub fn remove_note(note_id: &str) {
+    let mut index = INDEX.lock().unwrap();
+    if index.built {
+        index.remove_note(note_id);
+    }
+}
+
+/// Indexes a PDF's extracted per-page text, so imported PDFs are searchable.
+pub fn index_pdf_text(pdf_id: &str, text_pages: &[String]) {
+    let mut index = INDEX.lock().unwrap();
+    if !index.built {
+        return;
+    }
+    if !index.note_tags.contains_key(pdf_id) {
+        index.note_tags.insert(pdf_id.to_string(), vec![]);
+        index.total_docs += 1;
+    }
+    for (page_num, text) in text_pages.iter().enumerate() {
+        index.index_text(pdf_id, &format!("page_{}", page_num), text);
+    }
+}
+
+/// Also indexes a PDF's annotation contents, so highlighted/commented text is searchable.
+pub fn index_pdf_annotations(pdf_id: &str, annotations: &[crate::PDFAnnotation]) {
+    let mut index = INDEX.lock().unwrap();
+    if !index.built {
+        return;
+    }
+    if !index.note_tags.contains_key(pdf_id) {
+        index.note_tags.insert(pdf_id.to_string(), vec![]);
+        index.total_docs += 1;
+    }
+    for annotation in annotations {
+        if let Some(content) = &annotation.content {
+            index.index_text(pdf_id, &annotation.id, content);
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn search_notes(query: String, tags_filter: Option<Vec<String>>) -> Result<Vec<SearchResult>, String> {
+    ensure_built().await?;
+    let index = INDEX.lock().unwrap();
+
+    let terms = tokenize(&query);
+    if terms.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let n = index.total_docs.max(1) as f64;
+    let mut scores: HashMap<(String, String), f64> = HashMap::new();
+
+    for term in &terms {
+        let df = index.document_frequency(term);
+        if df == 0 {
+            continue;
+        }
+        let idf = (n / df as f64).ln();
+        if let Some(postings) = index.postings.get(term) {
+            for posting in postings {
+                let key = (posting.note_id.clone(), posting.block_id.clone());
+                *scores.entry(key).or_insert(0.0) += posting.term_frequency as f64 * idf;
+            }
+        }
+    }
+
+    let mut results: Vec<SearchResult> = scores
+        .into_iter()
+        .filter(|((note_id, _), _)| matches_tags_filter(&index, note_id, &tags_filter))
+        .map(|((note_id, block_id), score)| {
+            let snippet = index
+                .block_text
+                .get(&(note_id.clone(), block_id.clone()))
+                .cloned()
+                .unwrap_or_default();
+            SearchResult {
+                note_id,
+                block_id,
+                score,
+                snippet,
+            }
+        })
+        .collect();
+
+    results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap());
+    Ok(results)
+}
+
+fn matches_tags_filter(index: &SearchIndex, note_id: &str, tags_filter: &Option<Vec<String>>) -> bool {
+    match tags_filter {
+        None => true,
+        Some(required) if required.is_empty() => true,
+        Some(required) => match index.note_tags.get(note_id) {
+            Some(tags) => required.iter().all(|t| tags.contains(t)),
+            None => false,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn note(id: &str, content: &str, tags: Option<Vec<String>>) -> Note {
+        Note {
+            id: id.to_string(),
+            title: id.to_string(),
+            blocks: vec![Block {
+                id: "block_1".to_string(),
+                r#type: "paragraph".to_string(),
+                content: content.to_string(),
+                checked: None,
+                file_path: None,
+                children: None,
+                order: 0,
+            }],
+            created_at: "0".to_string(),
+            updated_at: "0".to_string(),
+            tags,
+        }
+    }
+
+    #[test]
+    fn counts_term_and_document_frequency_per_note() {
+        let mut index = SearchIndex::default();
+        index.rebuild_from(
+            vec![
+                note("note_rust", "rust rust programming", None),
+                note("note_other", "something unrelated", None),
+            ],
+            vec![],
+        );
+
+        let df = index.document_frequency("rust");
+        assert_eq!(df, 1);
+
+        let postings = index.postings.get("rust").unwrap();
+        assert_eq!(postings.len(), 1);
+        assert_eq!(postings[0].note_id, "note_rust");
+        assert_eq!(postings[0].term_frequency, 2);
+    }
+
+    #[test]
+    fn remove_note_drops_its_postings_and_doc_count() {
+        let mut index = SearchIndex::default();
+        index.rebuild_from(vec![note("note_a", "hello world", None)], vec![]);
+        assert_eq!(index.total_docs, 1);
+
+        index.remove_note("note_a");
+        assert_eq!(index.total_docs, 0);
+        assert_eq!(index.document_frequency("hello"), 0);
+    }
+
+    #[test]
+    fn index_text_is_idempotent_on_reindex() {
+        let mut index = SearchIndex::default();
+        index.index_text("note_a", "block_1", "duplicate duplicate");
+        index.index_text("note_a", "block_1", "duplicate duplicate");
+
+        let postings = index.postings.get("duplicate").unwrap();
+        assert_eq!(postings.len(), 1);
+        assert_eq!(postings[0].term_frequency, 2);
+    }
+
+    #[test]
+    fn tags_filter_requires_every_requested_tag() {
+        let mut index = SearchIndex::default();
+        index.rebuild_from(
+            vec![note(
+                "note_tagged",
+                "content",
+                Some(vec!["work".to_string(), "urgent".to_string()]),
+            )],
+            vec![],
+        );
+
+        assert!(matches_tags_filter(&index, "note_tagged", &None));
+        assert!(matches_tags_filter(
+            &index,
+            "note_tagged",
+            &Some(vec!["work".to_string()])
+        ));
+        assert!(!matches_tags_filter(
+            &index,
+            "note_tagged",
+            &Some(vec!["work".to_string(), "missing".to_string()])
+        ));
+    }
+}
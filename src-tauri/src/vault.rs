@@ -0,0 +1,127 @@
+// Recursive vault import: walks a directory tree honoring .gitignore/.ignore
+// rules and imports every Markdown file found as a note.
+
+use crate::error::AppError;
+use crate::get_app_subdir;
+use crate::markdown;
+use ignore::WalkBuilder;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+/// Tracks, per imported root, which files have already been imported and at
+/// what modified time, so re-running the import only picks up new or
+/// changed files instead of re-importing everything.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ImportManifest {
+    imported: HashMap<String, u64>,
+}
+
+fn manifest_file_name(root: &Path) -> String {
+    let mut hasher = DefaultHasher::new();
+    root.hash(&mut hasher);
+    format!("{:x}.json", hasher.finish())
+}
+
+async fn load_manifest(root: &Path) -> Result<ImportManifest, AppError> {
+    let manifest_path = get_app_subdir("imports").await?.join(manifest_file_name(root));
+    if !manifest_path.exists() {
+        return Ok(ImportManifest::default());
+    }
+    let content = tokio::fs::read_to_string(manifest_path).await?;
+    Ok(serde_json::from_str(&content).unwrap_or_default())
+}
+
+async fn save_manifest(root: &Path, manifest: &ImportManifest) -> Result<(), AppError> {
+    let manifest_path = get_app_subdir("imports").await?.join(manifest_file_name(root));
+    let json = serde_json::to_vec_pretty(manifest)?;
+    crate::write_atomic(&manifest_path, &json).await
+}
+
+/// Recursively collects every `.md` file under `root`, honoring
+/// `.gitignore`/`.ignore` rules and skipping hidden files. Shared by
+/// `import_directory` and the resumable vault-import job so both walks
+/// agree on which files count as part of the vault.
+pub(crate) fn collect_markdown_files(root: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = WalkBuilder::new(root)
+        .hidden(true)
+        .require_git(false)
+        .build()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.into_path())
+        .filter(|path| path.extension().and_then(|e| e.to_str()) == Some("md"))
+        .collect();
+    files.sort();
+    files
+}
+
+fn modified_secs(path: &Path) -> u64 {
+    std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Derives tags from the path components between `root` and the file, so
+/// `root/projects/flow-notes/todo.md` gets tags `["projects", "flow-notes"]`.
+fn tags_from_path(root: &Path, file: &Path) -> Vec<String> {
+    let Ok(relative) = file.strip_prefix(root) else {
+        return vec![];
+    };
+    relative
+        .parent()
+        .map(|dir| {
+            dir.components()
+                .filter_map(|c| c.as_os_str().to_str())
+                .map(|s| s.to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Recursively imports every `.md` file under `root`, respecting
+/// `.gitignore`/`.ignore` rules and skipping hidden files. Returns the ids
+/// of the notes that were imported or re-imported on this run.
+#[tauri::command]
+pub async fn import_directory(root: String) -> Result<Vec<String>, AppError> {
+    let root = PathBuf::from(root);
+    let mut manifest = load_manifest(&root).await?;
+    let mut imported_ids = Vec::new();
+
+    for path in collect_markdown_files(&root) {
+        let path = path.as_path();
+        let key = path.to_string_lossy().to_string();
+        let mtime = modified_secs(path);
+        if manifest.imported.get(&key) == Some(&mtime) {
+            continue; // already imported and unchanged
+        }
+
+        let Ok(text) = tokio::fs::read_to_string(path).await else {
+            continue;
+        };
+        let title = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("Untitled")
+            .to_string();
+        let tags = tags_from_path(&root, path);
+
+        let mut note = markdown::import_markdown(text, title)
+            .await
+            .map_err(AppError::MarkdownImport)?;
+        if !tags.is_empty() {
+            note.tags = Some(tags);
+            crate::save_note(note.clone()).await?;
+        }
+
+        manifest.imported.insert(key, mtime);
+        imported_ids.push(note.id);
+    }
+
+    save_manifest(&root, &manifest).await?;
+    Ok(imported_ids)
+}
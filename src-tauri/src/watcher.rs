@@ -0,0 +1,133 @@
+// Background filesystem watcher so edits made outside the app (a text
+// editor, a Dropbox sync) show up without a manual refresh.
+
+use lazy_static::lazy_static;
+use notify::{recommended_watcher, Event, EventKind, RecursiveMode, Watcher};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::RecvTimeoutError;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+/// How long the watcher must go without any event, on any watched path,
+/// before it emits the pending batch.
+const DEBOUNCE_WINDOW: Duration = Duration::from_millis(300);
+/// How long a path stays "ours" after `mark_written`, to suppress the echo
+/// of our own atomic-rename writes coming back through the watcher.
+const SUPPRESS_WINDOW: Duration = Duration::from_secs(1);
+
+lazy_static! {
+    static ref RECENTLY_WRITTEN: Mutex<HashMap<PathBuf, Instant>> = Mutex::new(HashMap::new());
+}
+
+/// Called by the storage layer just before it renames a file into place (or
+/// removes one), so the watcher thread knows to ignore the event it causes.
+pub fn mark_written(path: PathBuf) {
+    RECENTLY_WRITTEN.lock().unwrap().insert(path, Instant::now());
+}
+
+fn is_self_originated(path: &Path) -> bool {
+    let mut recently_written = RECENTLY_WRITTEN.lock().unwrap();
+    match recently_written.remove(path) {
+        Some(at) => at.elapsed() < SUPPRESS_WINDOW,
+        None => false,
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Change {
+    Changed,
+    Removed,
+}
+
+/// Spawns the watcher on a dedicated thread; `notify`'s callback API is
+/// synchronous, so it doesn't fit naturally into the async command runtime.
+pub fn start(app_handle: AppHandle) {
+    let Some(base_dir) = dirs::data_dir().map(|d| d.join("flow-notes")) else {
+        tracing::warn!("no app data directory; file watcher not started");
+        return;
+    };
+    let notes_dir = base_dir.join("notes");
+    let pdfs_dir = base_dir.join("pdfs");
+
+    std::thread::spawn(move || {
+        let (tx, rx) = std::sync::mpsc::channel::<Event>();
+
+        let mut watcher = match recommended_watcher(move |res: notify::Result<Event>| {
+            if let Ok(event) = res {
+                let _ = tx.send(event);
+            }
+        }) {
+            Ok(w) => w,
+            Err(err) => {
+                tracing::warn!(error = %err, "failed to start file watcher");
+                return;
+            }
+        };
+
+        if watcher.watch(&notes_dir, RecursiveMode::NonRecursive).is_err()
+            || watcher.watch(&pdfs_dir, RecursiveMode::NonRecursive).is_err()
+        {
+            tracing::warn!("failed to watch notes/pdfs directories");
+            return;
+        }
+
+        let mut pending: HashMap<PathBuf, Change> = HashMap::new();
+        let mut last_event_at = Instant::now();
+
+        loop {
+            match rx.recv_timeout(DEBOUNCE_WINDOW) {
+                Ok(event) => {
+                    for path in event.paths {
+                        if path.extension().and_then(|e| e.to_str()) == Some("tmp") {
+                            continue;
+                        }
+                        if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                            continue;
+                        }
+                        if is_self_originated(&path) {
+                            continue;
+                        }
+
+                        let change = match event.kind {
+                            EventKind::Remove(_) => Change::Removed,
+                            _ => Change::Changed,
+                        };
+                        pending.insert(path, change);
+                    }
+                    last_event_at = Instant::now();
+                }
+                Err(RecvTimeoutError::Timeout) => {
+                    if !pending.is_empty() && last_event_at.elapsed() >= DEBOUNCE_WINDOW {
+                        flush(&app_handle, &notes_dir, &pdfs_dir, std::mem::take(&mut pending));
+                    }
+                }
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        }
+    });
+}
+
+fn flush(
+    app_handle: &AppHandle,
+    notes_dir: &Path,
+    pdfs_dir: &Path,
+    pending: HashMap<PathBuf, Change>,
+) {
+    for (path, change) in pending {
+        let Some(id) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+
+        if path.starts_with(notes_dir) {
+            let event = match change {
+                Change::Changed => "note-changed",
+                Change::Removed => "note-deleted",
+            };
+            let _ = app_handle.emit_all(event, id);
+        } else if path.starts_with(pdfs_dir) {
+            let _ = app_handle.emit_all("pdf-changed", id);
+        }
+    }
+}